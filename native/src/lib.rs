@@ -1,16 +1,120 @@
 use neon::prelude::*;
+use neon::types::buffer::TypedArray;
 
 use ethabi::{
     token::{LenientTokenizer, Token, Tokenizer},
-    Contract, Error, Function, ParamType,
+    Contract, Error, Function, Hash, ParamType, RawLog,
 };
 
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Arc, Mutex},
+};
 
 use once_cell::sync::OnceCell;
 
 static INSTANCE: OnceCell<Mutex<HashMap<String, Contract>>> = OnceCell::new();
 
+// The raw ABI JSON, kept alongside the parsed `Contract`. `ethabi::ParamType::Tuple`
+// only keeps component *types*, discarding the `components[].name` entries from the
+// source ABI, so struct/tuple encoding recovers real field names from here instead
+// (see `named_components`).
+static ABI_JSON: OnceCell<Mutex<HashMap<String, serde_json::Value>>> = OnceCell::new();
+
+// Small error-mapping layer, in the spirit of the miette::Result-to-JS conversion used
+// by the cozo nodejs binding: turns any Displayable error (ethabi::Error, hex decode
+// failures, ...) into a catchable JS exception instead of aborting the process.
+trait OrThrow<T> {
+    fn or_throw<'a, C: Context<'a>>(self, cx: &mut C) -> NeonResult<T>;
+}
+
+impl<T, E: std::fmt::Display> OrThrow<T> for Result<T, E> {
+    fn or_throw<'a, C: Context<'a>>(self, cx: &mut C) -> NeonResult<T> {
+        self.map_err(|e| match cx.throw_error::<_, JsValue>(e.to_string()) {
+            Err(throw) => throw,
+            Ok(_) => unreachable!(),
+        })
+    }
+}
+
+// Same idea for lookup misses (`functions_by_name` returning empty, a missing contract
+// `id`, ...), which surface as `None` rather than an `Err`.
+trait OrThrowMsg<T> {
+    fn or_throw_msg<'a, C: Context<'a>>(self, cx: &mut C, msg: &str) -> NeonResult<T>;
+}
+
+impl<T> OrThrowMsg<T> for Option<T> {
+    fn or_throw_msg<'a, C: Context<'a>>(self, cx: &mut C, msg: &str) -> NeonResult<T> {
+        match self {
+            Some(v) => Ok(v),
+            None => match cx.throw_error::<_, JsValue>(msg) {
+                Err(throw) => Err(throw),
+                Ok(_) => unreachable!(),
+            },
+        }
+    }
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Keccak};
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// The canonical `name(type1,type2,...)` signature of `function`, the string its 4-byte
+// selector is hashed from. Only the inputs appear in it, never the outputs.
+fn canonical_signature(function: &Function) -> String {
+    let inputs = function.inputs.iter().map(|p| p.kind.to_string()).collect::<Vec<_>>().join(",");
+    format!("{}({})", function.name, inputs)
+}
+
+// `signature` may be a bare function name (`transfer`) or a full canonical signature
+// (`transfer(address,uint256)`). A bare name resolves directly when unambiguous; an
+// overloaded name requires the full signature to pick the right candidate.
+fn get_function(cx: &mut FunctionContext, id: &str, signature: &str) -> NeonResult<Function> {
+    let contracts = INSTANCE.get().unwrap().lock().unwrap();
+    let contract = contracts
+        .get(id)
+        .or_throw_msg(cx, &format!("no contract loaded for id `{}`", id))?;
+
+    let name = signature.split('(').next().unwrap_or(signature);
+    let candidates = contract.functions_by_name(name).or_throw(cx)?;
+
+    let function = if candidates.len() == 1 {
+        &candidates[0]
+    } else {
+        candidates
+            .iter()
+            .find(|f| canonical_signature(f) == signature)
+            .or_throw_msg(
+                cx,
+                &format!("no overload of `{}` matches signature `{}`", name, signature),
+            )?
+    };
+    Ok(function.clone())
+}
+
+fn get_event(cx: &mut FunctionContext, id: &str, signature: &str) -> NeonResult<ethabi::Event> {
+    let contracts = INSTANCE.get().unwrap().lock().unwrap();
+    let contract = contracts
+        .get(id)
+        .or_throw_msg(cx, &format!("no contract loaded for id `{}`", id))?;
+    let event = contract
+        .events_by_name(signature)
+        .or_throw(cx)?
+        .first()
+        .or_throw_msg(cx, &format!("event not found: {}", signature))?;
+    Ok(event.clone())
+}
+
 fn load_abi(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     let id_h: Handle<JsString> = cx.argument(0)?;
     let id = id_h.downcast::<JsString>().unwrap().value();
@@ -18,70 +122,199 @@ fn load_abi(mut cx: FunctionContext) -> JsResult<JsBoolean> {
     let abi_json_h: Handle<JsString> = cx.argument(1)?;
     let abi_json = abi_json_h.downcast::<JsString>().unwrap().value();
 
-    INSTANCE
-        .get()
-        .unwrap()
-        .lock()
-        .unwrap()
-        .insert(id, Contract::load(abi_json.as_bytes()).unwrap());
+    let contract = Contract::load(abi_json.as_bytes()).or_throw(&mut cx)?;
+    let abi_value: serde_json::Value = serde_json::from_str(&abi_json).or_throw(&mut cx)?;
+
+    INSTANCE.get().unwrap().lock().unwrap().insert(id.clone(), contract);
+    ABI_JSON.get().unwrap().lock().unwrap().insert(id, abi_value);
     Ok(cx.boolean(true))
 }
 
+// The canonical type signature ethabi would derive for `param_json`, e.g. a tuple
+// `{"type": "tuple[]", "components": [{"type": "uint256"}, {"type": "address"}]}`
+// becomes `"(uint256,address)[]"`. Used to match a raw ABI entry against a
+// `Function`'s `canonical_signature` without re-deriving `ParamType`.
+fn json_type_signature(param_json: &serde_json::Value) -> String {
+    let ty = param_json.get("type").and_then(|t| t.as_str()).unwrap_or("");
+    match ty.strip_prefix("tuple") {
+        Some(suffix) => {
+            let inner = json_components(param_json)
+                .iter()
+                .map(json_type_signature)
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("({}){}", inner, suffix)
+        }
+        None => ty.to_string(),
+    }
+}
+
+// Finds the raw ABI entry for `function`, matched by its full canonical signature
+// (the same one `get_function` uses to disambiguate overloads) rather than just name
+// and arity, so two overloads that share a name and argument count don't get their
+// tuple components' names recovered from the wrong ABI entry.
+fn function_json<'a>(abi: &'a serde_json::Value, function: &Function) -> Option<&'a serde_json::Value> {
+    abi.as_array()?.iter().find(|entry| {
+        let is_function = entry
+            .get("type")
+            .and_then(|t| t.as_str())
+            .map(|t| t == "function")
+            .unwrap_or(true);
+        let name_matches = entry.get("name").and_then(|n| n.as_str()) == Some(function.name.as_str());
+        let signature_matches = entry
+            .get("inputs")
+            .and_then(|i| i.as_array())
+            .map(|inputs| {
+                let types = inputs.iter().map(json_type_signature).collect::<Vec<_>>().join(",");
+                format!("{}({})", function.name, types) == canonical_signature(function)
+            })
+            .unwrap_or(false);
+        is_function && name_matches && signature_matches
+    })
+}
+
+// The raw `inputs` entries for `function`'s own parameters, i.e. the
+// per-argument json nodes `tokenize`/`tokenize_struct` need to recover tuple field names.
+fn function_inputs_json(id: &str, function: &Function) -> Vec<serde_json::Value> {
+    let abis = ABI_JSON.get().unwrap().lock().unwrap();
+    abis.get(id)
+        .and_then(|abi| function_json(abi, function))
+        .and_then(|entry| entry.get("inputs"))
+        .and_then(|inputs| inputs.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+// The raw `outputs` entries for `function`'s own return values, mirroring
+// `function_inputs_json` but for `outputs`.
+fn function_outputs_json(id: &str, function: &Function) -> Vec<serde_json::Value> {
+    let abis = ABI_JSON.get().unwrap().lock().unwrap();
+    abis.get(id)
+        .and_then(|abi| function_json(abi, function))
+        .and_then(|entry| entry.get("outputs"))
+        .and_then(|outputs| outputs.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+// `param_json`'s own `components` array, if it has one (i.e. `param_json` describes a
+// tuple or an array of tuples).
+fn json_components(param_json: &serde_json::Value) -> &[serde_json::Value] {
+    param_json
+        .get("components")
+        .and_then(|c| c.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
 fn remove_hex_prefix(data_hex: &str) -> &str {
-    // Remove any 0x prefix
-    match &data_hex[..2] {
-        "0x" => &data_hex[2..],
-        _ => &data_hex,
+    // Remove any 0x prefix. Guard the slice: a too-short string (e.g. "", "a") would
+    // otherwise panic on `&data_hex[..2]` and abort the whole process.
+    if data_hex.len() >= 2 && &data_hex[..2] == "0x" {
+        &data_hex[2..]
+    } else {
+        data_hex
+    }
+}
+
+fn strip_selector(calldata: Vec<u8>) -> Result<Vec<u8>, Error> {
+    if calldata.len() < 4 {
+        return Err(Error::InvalidData);
+    }
+    Ok(calldata[4..].to_vec())
+}
+
+// Calldata can arrive as a `Buffer`/`Uint8Array` or, as before, a hex string.
+fn read_calldata(value: &Handle<JsValue>, cx: &mut FunctionContext) -> Result<Vec<u8>, Error> {
+    if let Ok(buf) = value.downcast::<JsBuffer>() {
+        return Ok(buf.as_slice(cx).to_vec());
+    }
+    if let Ok(arr) = value.downcast::<JsUint8Array>() {
+        return Ok(arr.as_slice(cx).to_vec());
     }
+    let hex_str = value.downcast::<JsString>().map_err(|_| Error::InvalidData)?.value();
+    hex::decode(remove_hex_prefix(&hex_str)).map_err(|_| Error::InvalidData)
 }
 
-fn remove_bytes4(data_hex: &str) -> &str {
-    // Remove any 0x prefix
-    let s = remove_hex_prefix(&data_hex);
-    &s[8..]
+// Optional trailing boolean argument that opts into returning `Bytes`/`FixedBytes`
+// output (and raw calldata) as `Buffer`s instead of hex strings.
+fn as_buffer_flag(cx: &mut FunctionContext, index: i32) -> bool {
+    cx.argument_opt(index)
+        .and_then(|v| v.downcast::<JsBoolean>().ok())
+        .map(|b| b.value())
+        .unwrap_or(false)
 }
 
 fn tokenize_address(value: &Handle<JsValue>) -> Result<[u8; 20], Error> {
-    let arg = value.downcast::<JsString>().unwrap().value();
+    let arg = value.downcast::<JsString>().map_err(|_| Error::InvalidData)?.value();
     LenientTokenizer::tokenize_address(remove_hex_prefix(&arg))
 }
 
 fn tokenize_string(value: &Handle<JsValue>) -> Result<String, Error> {
-    let arg = value.downcast::<JsString>().unwrap().value();
+    let arg = value.downcast::<JsString>().map_err(|_| Error::InvalidData)?.value();
     LenientTokenizer::tokenize_string(&arg)
 }
 
 fn tokenize_bool(value: &Handle<JsValue>) -> Result<bool, Error> {
-    let arg = value.downcast::<JsBoolean>().unwrap().value();
+    let arg = value.downcast::<JsBoolean>().map_err(|_| Error::InvalidData)?.value();
     Ok(arg)
 }
 
-fn tokenize_bytes(value: &Handle<JsValue>) -> Result<Vec<u8>, Error> {
-    let arg = value.downcast::<JsString>().unwrap().value();
+// Reads raw bytes straight off a `Buffer`/`Uint8Array` when given one, avoiding a
+// hex-string round trip; falls back to the existing hex-string behavior otherwise.
+fn read_byte_value(value: &Handle<JsValue>, cx: &mut FunctionContext) -> Option<Vec<u8>> {
+    if let Ok(buf) = value.downcast::<JsBuffer>() {
+        return Some(buf.as_slice(cx).to_vec());
+    }
+    if let Ok(arr) = value.downcast::<JsUint8Array>() {
+        return Some(arr.as_slice(cx).to_vec());
+    }
+    None
+}
+
+fn tokenize_bytes(value: &Handle<JsValue>, cx: &mut FunctionContext) -> Result<Vec<u8>, Error> {
+    if let Some(bytes) = read_byte_value(value, cx) {
+        return Ok(bytes);
+    }
+    let arg = value.downcast::<JsString>().map_err(|_| Error::InvalidData)?.value();
     LenientTokenizer::tokenize_bytes(remove_hex_prefix(&arg))
 }
 
-fn tokenize_fixed_bytes(value: &Handle<JsValue>, len: usize) -> Result<Vec<u8>, Error> {
-    let arg = value.downcast::<JsString>().unwrap().value();
+fn tokenize_fixed_bytes(
+    value: &Handle<JsValue>,
+    len: usize,
+    cx: &mut FunctionContext,
+) -> Result<Vec<u8>, Error> {
+    if let Some(bytes) = read_byte_value(value, cx) {
+        // Mirror the length check `LenientTokenizer::tokenize_fixed_bytes` already
+        // does on the hex-string path below: a Buffer of the wrong size would
+        // otherwise silently produce corrupt calldata (too short) or panic in
+        // ethabi's word-padding (too long) instead of failing here.
+        if bytes.len() != len {
+            return Err(Error::InvalidData);
+        }
+        return Ok(bytes);
+    }
+    let arg = value.downcast::<JsString>().map_err(|_| Error::InvalidData)?.value();
     LenientTokenizer::tokenize_fixed_bytes(remove_hex_prefix(&arg), len)
 }
 
 fn tokenize_uint(value: &Handle<JsValue>) -> Result<[u8; 32], Error> {
     let str = if value.is_a::<JsNumber>() {
-        let arg = value.downcast::<JsNumber>().unwrap().value();
+        let arg = value.downcast::<JsNumber>().map_err(|_| Error::InvalidData)?.value();
         arg.to_string()
     } else {
-        value.downcast::<JsString>().unwrap().value()
+        value.downcast::<JsString>().map_err(|_| Error::InvalidData)?.value()
     };
     LenientTokenizer::tokenize_uint(&str)
 }
 
 fn tokenize_int(value: &Handle<JsValue>) -> Result<[u8; 32], Error> {
     let str = if value.is_a::<JsNumber>() {
-        let arg = value.downcast::<JsNumber>().unwrap().value();
+        let arg = value.downcast::<JsNumber>().map_err(|_| Error::InvalidData)?.value();
         arg.to_string()
     } else {
-        value.downcast::<JsString>().unwrap().value()
+        value.downcast::<JsString>().map_err(|_| Error::InvalidData)?.value()
     };
     LenientTokenizer::tokenize_int(&str)
 }
@@ -89,74 +322,149 @@ fn tokenize_int(value: &Handle<JsValue>) -> Result<[u8; 32], Error> {
 fn tokenize_array(
     value: &Handle<JsValue>,
     param: &ParamType,
+    param_json: Option<&serde_json::Value>,
     cx: &mut FunctionContext,
-) -> Result<Vec<Token>, Error> {
-    let arr = value.downcast::<JsArray>().unwrap().to_vec(cx).unwrap();
+) -> anyhow::Result<Vec<Token>> {
+    let arr = value.downcast::<JsArray>().map_err(|_| Error::InvalidData)?.to_vec(cx).map_err(|_| Error::InvalidData)?;
     let mut result = vec![];
-    for (_i, v) in arr.iter().enumerate() {
-        let token = tokenize(param, v, cx)?;
+    for v in arr.iter() {
+        let token = tokenize(param, param_json, v, cx)?;
         result.push(token)
     }
     Ok(result)
 }
 
+// Components nested inside a `ParamType::Tuple` don't carry their own names (only the
+// top-level `Param` does) — but `param_json`'s `components` array does, straight from
+// the ABI. Pairs them up positionally with the already-parsed `ParamType`s to rebuild
+// real `Param`s, falling back to an unnamed `Param` per component when `param_json` is
+// missing (e.g. the contract wasn't loaded through `load_abi`, so there's nothing to
+// recover names from) — in which case the object-keyed branch of `tokenize_struct`
+// throws for the unnamed component and only the positional array form can be encoded.
+fn named_components(kinds: &[ParamType], param_json: Option<&serde_json::Value>) -> Vec<ethabi::Param> {
+    let components = param_json.map(json_components).unwrap_or(&[]);
+    kinds
+        .iter()
+        .enumerate()
+        .map(|(i, kind)| {
+            let name = components
+                .get(i)
+                .and_then(|c| c.get("name"))
+                .and_then(|n| n.as_str())
+                .unwrap_or_default()
+                .to_string();
+            ethabi::Param {
+                name,
+                kind: kind.clone(),
+                internal_type: None,
+            }
+        })
+        .collect()
+}
+
 fn tokenize_struct(
     value: &Handle<JsValue>,
-    param: &[ParamType],
+    params: &[ethabi::Param],
+    param_json: Option<&serde_json::Value>,
     cx: &mut FunctionContext,
-) -> Result<Vec<Token>, Error> {
-    let mut params = param.iter();
+) -> anyhow::Result<Vec<Token>> {
+    let components = param_json.map(json_components).unwrap_or(&[]);
     let mut result = vec![];
-    // If it's an array we assume it is in the correct order
     if value.is_a::<JsArray>() {
-        let arr = value.downcast::<JsArray>().unwrap().to_vec(cx).unwrap();
-        for (_i, v) in arr.iter().enumerate() {
-            let p = params.next().ok_or(Error::InvalidData)?;
-            let token = tokenize(p, v, cx)?;
+        // If it's an array we assume it is in the correct order
+        let arr = value.downcast::<JsArray>().map_err(|_| Error::InvalidData)?.to_vec(cx).map_err(|_| Error::InvalidData)?;
+        let mut params_iter = params.iter().enumerate();
+        for v in arr.iter() {
+            let (i, p) = params_iter
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("too many values for tuple with {} field(s)", params.len()))?;
+            let token = tokenize(&p.kind, components.get(i), v, cx)?;
+            result.push(token)
+        }
+    } else if value.is_a::<JsObject>() {
+        // Otherwise look each field up by its ABI component name, Solidity struct-literal style
+        let obj = value.downcast::<JsObject>().map_err(|_| Error::InvalidData)?;
+        for (i, param) in params.iter().enumerate() {
+            if param.name.is_empty() {
+                anyhow::bail!(
+                    "cannot encode tuple field {} by name: the loaded ABI doesn't name this component",
+                    i
+                );
+            }
+            let field: Handle<JsValue> = obj
+                .get(cx, param.name.as_str())
+                .map_err(|_| anyhow::anyhow!("failed to read struct field `{}`", param.name))?;
+            if field.is_a::<JsUndefined>() {
+                anyhow::bail!("missing struct field `{}`", param.name);
+            }
+            let token = tokenize(&param.kind, components.get(i), &field, cx)?;
             result.push(token)
         }
     } else {
-        panic!("Unsupported object structure, use an array of ordered values");
+        anyhow::bail!("expected an array or object for tuple argument");
     }
     Ok(result)
 }
 
 fn tokenize(
     param: &ParamType,
+    param_json: Option<&serde_json::Value>,
     value: &Handle<JsValue>,
     cx: &mut FunctionContext,
-) -> Result<Token, Error> {
-    match *param {
-        ParamType::Address => tokenize_address(value).map(|a| Token::Address(a.into())),
-        ParamType::String => tokenize_string(value).map(Token::String),
-        ParamType::Bool => tokenize_bool(value).map(Token::Bool),
-        ParamType::Bytes => tokenize_bytes(value).map(Token::Bytes),
-        ParamType::FixedBytes(len) => tokenize_fixed_bytes(value, len).map(Token::FixedBytes),
-        ParamType::Uint(_) => tokenize_uint(value).map(Into::into).map(Token::Uint),
-        ParamType::Int(_) => tokenize_int(value).map(Into::into).map(Token::Int),
-        ParamType::Array(ref p) => tokenize_array(value, p, cx).map(Token::Array),
-        ParamType::FixedArray(ref p, _len) => tokenize_array(value, p, cx).map(Token::FixedArray),
-        ParamType::Tuple(ref p) => tokenize_struct(value, p, cx).map(Token::Tuple),
-    }
+) -> anyhow::Result<Token> {
+    let token = match *param {
+        ParamType::Address => Token::Address(tokenize_address(value)?.into()),
+        ParamType::String => Token::String(tokenize_string(value)?),
+        ParamType::Bool => Token::Bool(tokenize_bool(value)?),
+        ParamType::Bytes => Token::Bytes(tokenize_bytes(value, cx)?),
+        ParamType::FixedBytes(len) => Token::FixedBytes(tokenize_fixed_bytes(value, len, cx)?),
+        ParamType::Uint(_) => Token::Uint(tokenize_uint(value)?.into()),
+        ParamType::Int(_) => Token::Int(tokenize_int(value)?.into()),
+        ParamType::Array(ref p) => Token::Array(tokenize_array(value, p, param_json, cx)?),
+        ParamType::FixedArray(ref p, _len) => Token::FixedArray(tokenize_array(value, p, param_json, cx)?),
+        ParamType::Tuple(ref p) => {
+            let params = named_components(p, param_json);
+            Token::Tuple(tokenize_struct(value, &params, param_json, cx)?)
+        }
+    };
+    Ok(token)
 }
 
-fn tokenize_out<'cx>(
+fn bytes_to_buffer<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    bytes: &[u8],
+) -> Result<Handle<'cx, JsBuffer>, Error> {
+    let mut buffer = JsBuffer::new(cx, bytes.len()).map_err(|_| Error::InvalidData)?;
+    buffer.as_mut_slice(cx).copy_from_slice(bytes);
+    Ok(buffer)
+}
+
+// Generic over `Context` (rather than tied to `FunctionContext`) so it can also run
+// inside a `TaskContext`, i.e. the `deferred.settle_with` callback of the async variants.
+// `as_buffer` opts into emitting `Token::Bytes`/`FixedBytes` as `Buffer`s instead of hex
+// strings, mirroring `tokenize_bytes`'s Buffer support on the encoding side.
+fn tokenize_out<'cx, C: Context<'cx>>(
     token: &ethabi::Token,
-    cx: &mut FunctionContext<'cx>,
+    as_buffer: bool,
+    cx: &mut C,
 ) -> Result<Handle<'cx, JsValue>, Error> {
     let value: Handle<JsValue> = match token {
         Token::Bool(b) => cx.boolean(*b).upcast(),
         Token::String(ref s) => cx.string(s.to_string()).upcast(),
         Token::Address(ref s) => cx.string(format!("0x{}", hex::encode(&s))).upcast(),
         Token::Bytes(ref bytes) | Token::FixedBytes(ref bytes) => {
-            cx.string(format!("0x{}", hex::encode(bytes))).upcast()
+            if as_buffer {
+                bytes_to_buffer(cx, bytes)?.upcast()
+            } else {
+                cx.string(format!("0x{}", hex::encode(bytes))).upcast()
+            }
         }
         Token::Uint(ref i) | Token::Int(ref i) => cx.string(i.to_string()).upcast(),
         // Arrays and Tuples will contain one of the above, or more arrays or tuples
         Token::Array(ref arr) | Token::FixedArray(ref arr) | Token::Tuple(ref arr) => {
             let value_array = JsArray::new(cx, arr.len() as u32);
             for (i, value) in arr.iter().enumerate() {
-                let result = tokenize_out(value, cx)?;
+                let result = tokenize_out(value, as_buffer, cx)?;
                 value_array.set(cx, i as u32, result).unwrap();
             }
             value_array.upcast()
@@ -165,21 +473,88 @@ fn tokenize_out<'cx>(
     Ok(value)
 }
 
+// Like `tokenize_out`, but for a `Token::Tuple` keys its components by ABI name
+// (recovered from `param_json`'s `components`, the same source `tokenize`/
+// `tokenize_struct` use on the encode side) instead of falling through to
+// `tokenize_out`'s plain positional array, recursing so a tuple nested inside an
+// array or another tuple is named too. Anything that isn't a tuple/array of tuples
+// is unaffected and renders exactly as `tokenize_out` would.
+fn named_token_out<'cx, C: Context<'cx>>(
+    token: &Token,
+    param_json: Option<&serde_json::Value>,
+    as_buffer: bool,
+    cx: &mut C,
+) -> Result<Handle<'cx, JsValue>, Error> {
+    match token {
+        Token::Tuple(ref arr) => {
+            let components = param_json.map(json_components).unwrap_or(&[]);
+            let obj = cx.empty_object();
+            for (i, value) in arr.iter().enumerate() {
+                let component_json = components.get(i);
+                let name = component_json
+                    .and_then(|c| c.get("name"))
+                    .and_then(|n| n.as_str())
+                    .filter(|n| !n.is_empty())
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| i.to_string());
+                let result = named_token_out(value, component_json, as_buffer, cx)?;
+                obj.set(cx, name.as_str(), result).unwrap();
+            }
+            Ok(obj.upcast())
+        }
+        Token::Array(ref arr) | Token::FixedArray(ref arr) => {
+            let value_array = JsArray::new(cx, arr.len() as u32);
+            for (i, value) in arr.iter().enumerate() {
+                let result = named_token_out(value, param_json, as_buffer, cx)?;
+                value_array.set(cx, i as u32, result).unwrap();
+            }
+            Ok(value_array.upcast())
+        }
+        _ => tokenize_out(token, as_buffer, cx),
+    }
+}
+
+// Keys arrays of top-level params (function outputs/inputs) by their ABI name instead
+// of position, recursing into nested tuples via `named_token_out` so struct fields
+// come back named at every depth, not just the outer level. `params_json` is the raw
+// `inputs`/`outputs` ABI entries for `params`, in the same order (see
+// `function_inputs_json`/`function_outputs_json`) - empty/missing entries just fall
+// back to positional keys, same as before per-component names were recovered.
+fn named_tokens2js<'cx, C: Context<'cx>>(
+    params: &[ethabi::Param],
+    tokens: &[Token],
+    params_json: &[serde_json::Value],
+    as_buffer: bool,
+    cx: &mut C,
+) -> Result<Handle<'cx, JsObject>, Error> {
+    let obj = cx.empty_object();
+    for (i, (param, token)) in params.iter().zip(tokens.iter()).enumerate() {
+        let value = named_token_out(token, params_json.get(i), as_buffer, cx)?;
+        let key = if param.name.is_empty() {
+            i.to_string()
+        } else {
+            param.name.clone()
+        };
+        obj.set(cx, key.as_str(), value).unwrap();
+    }
+    Ok(obj)
+}
+
 fn parse_tokens(
-    params: &[(ParamType, &Handle<JsValue>)],
+    params: &[(ParamType, Option<serde_json::Value>, &Handle<JsValue>)],
     cx: &mut FunctionContext,
 ) -> anyhow::Result<Vec<Token>> {
     params
         .iter()
-        .map(|&(ref param, value)| tokenize(param, value, cx))
-        .collect::<Result<_, _>>()
-        .map_err(From::from)
+        .map(|(param, param_json, value)| tokenize(param, param_json.as_ref(), value, cx))
+        .collect()
 }
 
-fn encode_input(mut cx: FunctionContext) -> JsResult<JsString> {
+fn encode_input(mut cx: FunctionContext) -> JsResult<JsValue> {
     // ID (0)
     // function name (1)
     // args array (2)
+    // asBuffer flag (3)
     let id_h: Handle<JsString> = cx.argument(0)?;
     let id = id_h.downcast::<JsString>().unwrap().value();
 
@@ -188,89 +563,347 @@ fn encode_input(mut cx: FunctionContext) -> JsResult<JsString> {
 
     let function_signature = function_signature_h.downcast::<JsString>().unwrap().value();
     let args_vec: Vec<Handle<JsValue>> = args_h.to_vec(&mut cx)?;
+    let as_buffer = as_buffer_flag(&mut cx, 3);
 
-    let function: Function = INSTANCE
-        .get()
-        .unwrap()
-        .lock()
-        .unwrap()
-        .get(&id)
-        .unwrap()
-        .functions_by_name(&function_signature)
-        .unwrap()[0]
-        .clone();
+    let function = get_function(&mut cx, &id, &function_signature)?;
+    let inputs_json = function_inputs_json(&id, &function);
 
     let params: Vec<_> = function
         .inputs
         .iter()
-        .map(|param| param.kind.clone())
-        .zip(args_vec.iter().map(|v| v as &Handle<JsValue>))
+        .enumerate()
+        .zip(args_vec.iter())
+        .map(|((i, param), value)| (param.kind.clone(), inputs_json.get(i).cloned(), value))
         .collect();
-    let tokens = parse_tokens(&params, &mut cx).unwrap();
-    let encoded = function.encode_input(&tokens).unwrap();
-    Ok(cx.string(hex::encode(&encoded)))
+    let tokens = parse_tokens(&params, &mut cx).or_throw(&mut cx)?;
+    let encoded = function.encode_input(&tokens).or_throw(&mut cx)?;
+    if as_buffer {
+        Ok(bytes_to_buffer(&mut cx, &encoded).or_throw(&mut cx)?.upcast())
+    } else {
+        Ok(cx.string(hex::encode(&encoded)).upcast())
+    }
 }
 
 fn decode_output(mut cx: FunctionContext) -> JsResult<JsArray> {
     let id_h: Handle<JsString> = cx.argument(0)?;
     let function_signature_h: Handle<JsString> = cx.argument(1)?;
-    let data_h: Handle<JsString> = cx.argument(2)?;
+    let data_h: Handle<JsValue> = cx.argument(2)?;
 
     let id = id_h.downcast::<JsString>().unwrap().value();
     let function_signature = function_signature_h.downcast::<JsString>().unwrap().value();
-    let data_hex = data_h.downcast::<JsString>().unwrap().value();
+    let as_buffer = as_buffer_flag(&mut cx, 3);
 
-    let function: Function = INSTANCE
-        .get()
-        .unwrap()
-        .lock()
-        .unwrap()
-        .get(&id)
-        .unwrap()
-        .functions_by_name(&function_signature)
-        .unwrap()[0]
-        .clone();
+    let function = get_function(&mut cx, &id, &function_signature)?;
 
-    let data: Vec<u8> = hex::decode(remove_hex_prefix(&data_hex)).unwrap();
-    let tokens = function.decode_output(&data).unwrap();
+    let data = read_calldata(&data_h, &mut cx).or_throw(&mut cx)?;
+    let tokens = function.decode_output(&data).or_throw(&mut cx)?;
 
     let result_array = JsArray::new(&mut cx, tokens.len() as u32);
 
     for (i, token) in tokens.iter().enumerate() {
-        let result = tokenize_out(token, &mut cx).unwrap();
+        let result = tokenize_out(token, as_buffer, &mut cx).or_throw(&mut cx)?;
         result_array.set(&mut cx, i as u32, result)?;
     }
 
     Ok(result_array)
 }
 
+fn decode_output_named(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let id_h: Handle<JsString> = cx.argument(0)?;
+    let function_signature_h: Handle<JsString> = cx.argument(1)?;
+    let data_h: Handle<JsValue> = cx.argument(2)?;
+
+    let id = id_h.downcast::<JsString>().unwrap().value();
+    let function_signature = function_signature_h.downcast::<JsString>().unwrap().value();
+    let as_buffer = as_buffer_flag(&mut cx, 3);
+
+    let function = get_function(&mut cx, &id, &function_signature)?;
+    let outputs_json = function_outputs_json(&id, &function);
+
+    let data = read_calldata(&data_h, &mut cx).or_throw(&mut cx)?;
+    let tokens = function.decode_output(&data).or_throw(&mut cx)?;
+
+    named_tokens2js(&function.outputs, &tokens, &outputs_json, as_buffer, &mut cx).or_throw(&mut cx)
+}
+
 fn decode_input(mut cx: FunctionContext) -> JsResult<JsArray> {
     let id_h: Handle<JsString> = cx.argument(0)?;
     let function_signature_h: Handle<JsString> = cx.argument(1)?;
-    let data_h: Handle<JsString> = cx.argument(2)?;
+    let data_h: Handle<JsValue> = cx.argument(2)?;
 
     let id = id_h.downcast::<JsString>().unwrap().value();
     let function_signature = function_signature_h.downcast::<JsString>().unwrap().value();
-    let data_hex = data_h.downcast::<JsString>().unwrap().value();
+    let as_buffer = as_buffer_flag(&mut cx, 3);
 
-    let function: Function = INSTANCE
-        .get()
-        .unwrap()
-        .lock()
-        .unwrap()
-        .get(&id)
-        .unwrap()
-        .functions_by_name(&function_signature)
-        .unwrap()[0]
-        .clone();
+    let function = get_function(&mut cx, &id, &function_signature)?;
 
-    let data: Vec<u8> = hex::decode(&remove_bytes4(&data_hex)).unwrap();
-    let tokens = function.decode_input(&data).unwrap();
+    let calldata = read_calldata(&data_h, &mut cx).or_throw(&mut cx)?;
+    let data = strip_selector(calldata).or_throw(&mut cx)?;
+    let tokens = function.decode_input(&data).or_throw(&mut cx)?;
 
     let result_array = JsArray::new(&mut cx, tokens.len() as u32);
 
     for (i, token) in tokens.iter().enumerate() {
-        let result = tokenize_out(token, &mut cx).unwrap();
+        let result = tokenize_out(token, as_buffer, &mut cx).or_throw(&mut cx)?;
+        result_array.set(&mut cx, i as u32, result)?;
+    }
+
+    Ok(result_array)
+}
+
+fn decode_input_named(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let id_h: Handle<JsString> = cx.argument(0)?;
+    let function_signature_h: Handle<JsString> = cx.argument(1)?;
+    let data_h: Handle<JsValue> = cx.argument(2)?;
+
+    let id = id_h.downcast::<JsString>().unwrap().value();
+    let function_signature = function_signature_h.downcast::<JsString>().unwrap().value();
+    let as_buffer = as_buffer_flag(&mut cx, 3);
+
+    let function = get_function(&mut cx, &id, &function_signature)?;
+    let inputs_json = function_inputs_json(&id, &function);
+
+    let calldata = read_calldata(&data_h, &mut cx).or_throw(&mut cx)?;
+    let data = strip_selector(calldata).or_throw(&mut cx)?;
+    let tokens = function.decode_input(&data).or_throw(&mut cx)?;
+
+    named_tokens2js(&function.inputs, &tokens, &inputs_json, as_buffer, &mut cx).or_throw(&mut cx)
+}
+
+// Resolves the target function purely from the calldata's leading 4-byte selector,
+// rather than from a name/signature the caller already knows. Useful when decoding
+// calldata pulled off-chain (e.g. from a transaction trace) where only the raw bytes
+// are available.
+fn decode_input_by_selector(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let id_h: Handle<JsString> = cx.argument(0)?;
+    let data_h: Handle<JsValue> = cx.argument(1)?;
+
+    let id = id_h.downcast::<JsString>().unwrap().value();
+    let as_buffer = as_buffer_flag(&mut cx, 2);
+
+    let calldata = read_calldata(&data_h, &mut cx).or_throw(&mut cx)?;
+    if calldata.len() < 4 {
+        return cx.throw_error("calldata shorter than a 4-byte selector");
+    }
+    let selector_bytes = &calldata[..4];
+
+    let function = {
+        let contracts = INSTANCE.get().unwrap().lock().unwrap();
+        let contract = contracts
+            .get(&id)
+            .or_throw_msg(&mut cx, &format!("no contract loaded for id `{}`", id))?;
+        contract
+            .functions()
+            .find(|f| selector(&canonical_signature(f)) == selector_bytes)
+            .or_throw_msg(
+                &mut cx,
+                &format!("no function matches selector 0x{}", hex::encode(selector_bytes)),
+            )?
+            .clone()
+    };
+
+    let data = strip_selector(calldata).or_throw(&mut cx)?;
+    let tokens = function.decode_input(&data).or_throw(&mut cx)?;
+    let inputs_json = function_inputs_json(&id, &function);
+    let params = named_tokens2js(&function.inputs, &tokens, &inputs_json, as_buffer, &mut cx).or_throw(&mut cx)?;
+
+    let result = cx.empty_object();
+    let name = cx.string(&function.name);
+    let signature = cx.string(function.signature());
+    result.set(&mut cx, "name", name)?;
+    result.set(&mut cx, "signature", signature)?;
+    result.set(&mut cx, "params", params)?;
+
+    Ok(result)
+}
+
+// Fixed-size pool backing the async variants below. Batch workloads (the motivating
+// use case for these) can fire off far more concurrent encode/decode calls than there
+// are cores; spawning a raw OS thread per call would let that flood exhaust the
+// process' thread limit instead of helping throughput, so every call is queued onto a
+// small, bounded set of long-lived worker threads instead.
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+const WORKER_THREADS: usize = 4;
+
+// `mpsc::Sender` is `Send` but not `Sync`, so it can't sit behind a `static` on its
+// own; wrap it in a `Mutex` (as `INSTANCE`/`ABI_JSON` already do for their contents)
+// to make the pool safe to reach from every calling thread.
+static WORKER_POOL: OnceCell<Mutex<mpsc::Sender<Job>>> = OnceCell::new();
+
+fn worker_pool() -> &'static Mutex<mpsc::Sender<Job>> {
+    WORKER_POOL.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+        for _ in 0..WORKER_THREADS {
+            let rx = Arc::clone(&rx);
+            std::thread::spawn(move || loop {
+                let job = match rx.lock().unwrap().recv() {
+                    Ok(job) => job,
+                    Err(_) => break,
+                };
+                job();
+            });
+        }
+        Mutex::new(tx)
+    })
+}
+
+fn spawn_on_pool<F: FnOnce() + Send + 'static>(job: F) {
+    worker_pool()
+        .lock()
+        .unwrap()
+        .send(Box::new(job))
+        .expect("async worker pool shut down unexpectedly");
+}
+
+// Async variants: the argument parsing/tokenizing (which needs the `FunctionContext`)
+// still happens on the main thread, but the heavyweight `ethabi` encode/decode call is
+// offloaded to the worker pool above and the result is marshaled back through a
+// `Channel`, keeping the V8 event loop free for high-throughput batch workloads.
+fn encode_input_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let id_h: Handle<JsString> = cx.argument(0)?;
+    let id = id_h.downcast::<JsString>().unwrap().value();
+
+    let function_signature_h: Handle<JsString> = cx.argument(1)?;
+    let args_h: Handle<JsArray> = cx.argument(2)?;
+
+    let function_signature = function_signature_h.downcast::<JsString>().unwrap().value();
+    let args_vec: Vec<Handle<JsValue>> = args_h.to_vec(&mut cx)?;
+    let as_buffer = as_buffer_flag(&mut cx, 3);
+
+    let function = get_function(&mut cx, &id, &function_signature)?;
+    let inputs_json = function_inputs_json(&id, &function);
+
+    let params: Vec<_> = function
+        .inputs
+        .iter()
+        .enumerate()
+        .zip(args_vec.iter())
+        .map(|((i, param), value)| (param.kind.clone(), inputs_json.get(i).cloned(), value))
+        .collect();
+    let tokens = parse_tokens(&params, &mut cx).or_throw(&mut cx)?;
+
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    spawn_on_pool(move || {
+        let result = function.encode_input(&tokens);
+        deferred.settle_with(&channel, move |mut cx| {
+            let encoded = result.or_throw(&mut cx)?;
+            if as_buffer {
+                Ok(bytes_to_buffer(&mut cx, &encoded).or_throw(&mut cx)?.upcast())
+            } else {
+                Ok(cx.string(hex::encode(&encoded)).upcast())
+            }
+        });
+    });
+
+    Ok(promise)
+}
+
+fn decode_output_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let id_h: Handle<JsString> = cx.argument(0)?;
+    let function_signature_h: Handle<JsString> = cx.argument(1)?;
+    let data_h: Handle<JsValue> = cx.argument(2)?;
+
+    let id = id_h.downcast::<JsString>().unwrap().value();
+    let function_signature = function_signature_h.downcast::<JsString>().unwrap().value();
+    let as_buffer = as_buffer_flag(&mut cx, 3);
+
+    let function = get_function(&mut cx, &id, &function_signature)?;
+    let data = read_calldata(&data_h, &mut cx).or_throw(&mut cx)?;
+
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    spawn_on_pool(move || {
+        let result = function.decode_output(&data);
+        deferred.settle_with(&channel, move |mut cx| {
+            let tokens = result.or_throw(&mut cx)?;
+            let result_array = JsArray::new(&mut cx, tokens.len() as u32);
+            for (i, token) in tokens.iter().enumerate() {
+                let value = tokenize_out(token, as_buffer, &mut cx).or_throw(&mut cx)?;
+                result_array.set(&mut cx, i as u32, value)?;
+            }
+            Ok(result_array)
+        });
+    });
+
+    Ok(promise)
+}
+
+fn decode_input_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let id_h: Handle<JsString> = cx.argument(0)?;
+    let function_signature_h: Handle<JsString> = cx.argument(1)?;
+    let data_h: Handle<JsValue> = cx.argument(2)?;
+
+    let id = id_h.downcast::<JsString>().unwrap().value();
+    let function_signature = function_signature_h.downcast::<JsString>().unwrap().value();
+    let as_buffer = as_buffer_flag(&mut cx, 3);
+
+    let function = get_function(&mut cx, &id, &function_signature)?;
+    let calldata = read_calldata(&data_h, &mut cx).or_throw(&mut cx)?;
+    let data = strip_selector(calldata).or_throw(&mut cx)?;
+
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    spawn_on_pool(move || {
+        let result = function.decode_input(&data);
+        deferred.settle_with(&channel, move |mut cx| {
+            let tokens = result.or_throw(&mut cx)?;
+            let result_array = JsArray::new(&mut cx, tokens.len() as u32);
+            for (i, token) in tokens.iter().enumerate() {
+                let value = tokenize_out(token, as_buffer, &mut cx).or_throw(&mut cx)?;
+                result_array.set(&mut cx, i as u32, value)?;
+            }
+            Ok(result_array)
+        });
+    });
+
+    Ok(promise)
+}
+
+fn decode_log(mut cx: FunctionContext) -> JsResult<JsArray> {
+    // ID (0)
+    // event signature (1)
+    // topics array (2)
+    // data (3)
+    let id_h: Handle<JsString> = cx.argument(0)?;
+    let event_signature_h: Handle<JsString> = cx.argument(1)?;
+    let topics_h: Handle<JsArray> = cx.argument(2)?;
+    let data_h: Handle<JsValue> = cx.argument(3)?;
+
+    let id = id_h.downcast::<JsString>().unwrap().value();
+    let event_signature = event_signature_h.downcast::<JsString>().unwrap().value();
+    let topics_vec: Vec<Handle<JsValue>> = topics_h.to_vec(&mut cx)?;
+    let as_buffer = as_buffer_flag(&mut cx, 4);
+
+    let event = get_event(&mut cx, &id, &event_signature)?;
+
+    let topics: Vec<Hash> = topics_vec
+        .iter()
+        .map(|t| -> Result<Hash, Error> {
+            let topic_hex = t.downcast::<JsString>().map_err(|_| Error::InvalidData)?.value();
+            let bytes = hex::decode(remove_hex_prefix(&topic_hex)).map_err(|_| Error::InvalidData)?;
+            // `Hash::from_slice` asserts its input is exactly 32 bytes and panics
+            // otherwise - check first so a truncated/malformed topic throws instead
+            // of aborting the process.
+            if bytes.len() != 32 {
+                return Err(Error::InvalidData);
+            }
+            Ok(Hash::from_slice(&bytes))
+        })
+        .collect::<Result<_, _>>()
+        .or_throw(&mut cx)?;
+    let data = read_calldata(&data_h, &mut cx).or_throw(&mut cx)?;
+
+    let log = event.parse_log(RawLog { topics, data }).or_throw(&mut cx)?;
+
+    let result_array = JsArray::new(&mut cx, log.params.len() as u32);
+    for (i, param) in log.params.iter().enumerate() {
+        let result = tokenize_out(&param.value, as_buffer, &mut cx).or_throw(&mut cx)?;
         result_array.set(&mut cx, i as u32, result)?;
     }
 
@@ -283,11 +916,19 @@ fn hello(mut cx: FunctionContext) -> JsResult<JsString> {
 
 register_module!(mut cx, {
     INSTANCE.set(Mutex::new(HashMap::new())).unwrap();
+    ABI_JSON.set(Mutex::new(HashMap::new())).unwrap();
 
     cx.export_function("hello", hello)?;
     cx.export_function("loadAbi", load_abi)?;
     cx.export_function("encodeInput", encode_input)?;
     cx.export_function("decodeInput", decode_input)?;
     cx.export_function("decodeOutput", decode_output)?;
+    cx.export_function("decodeOutputNamed", decode_output_named)?;
+    cx.export_function("decodeInputNamed", decode_input_named)?;
+    cx.export_function("decodeInputBySelector", decode_input_by_selector)?;
+    cx.export_function("decodeLog", decode_log)?;
+    cx.export_function("encodeInputAsync", encode_input_async)?;
+    cx.export_function("decodeOutputAsync", decode_output_async)?;
+    cx.export_function("decodeInputAsync", decode_input_async)?;
     Ok(())
 });